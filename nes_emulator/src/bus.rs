@@ -0,0 +1,199 @@
+/**
+ * The address/data bus the CPU is wired to.
+ *
+ * Real NES hardware does not give the CPU a flat 64KB RAM array: reads and
+ * writes to a given address are decoded and routed to whatever is mapped
+ * there (internal RAM, mirrored ranges, PPU/APU registers, or the
+ * cartridge mapper). `Bus` is the seam that lets `CPU` stay ignorant of
+ * which of those it is talking to; `FlatMemory` is the simplest possible
+ * implementation, a single flat array with no decoding at all.
+ */
+use simple_error::SimpleError;
+
+// Max address.
+const MEM_ADDR_MAX: u16 = 0xffff;
+const MEM_ADDR_SPACE_SIZE: usize = MEM_ADDR_MAX as usize + 1;
+
+// The read/write surface a CPU needs from whatever backs its address space.
+pub trait Bus {
+    fn read(&self, addr: u16) -> u8;
+
+    // Reads two bytes starting at |addr|. Little endian.
+    fn read16(&self, addr: u16) -> Result<u16, SimpleError>;
+
+    fn write(&mut self, addr: u16, val: u8);
+
+    fn write16(&mut self, addr: u16, val: u16) -> Result<(), SimpleError>;
+
+    fn write_range(&mut self, start_addr: u16, val: &[u8]) -> Result<(), SimpleError>;
+}
+
+// The simplest possible `Bus`: a single 64KB array with no address
+// decoding, mirroring, or memory-mapped I/O. This is what a bare CPU (no
+// PPU/APU/mapper attached) needs, and is the default backing memory for
+// `CPU`.
+pub struct FlatMemory {
+    // The maximum addressable memory is 64KB.
+    data: [u8; MEM_ADDR_SPACE_SIZE],
+}
+
+impl FlatMemory {
+    pub fn new() -> Self {
+        FlatMemory {
+            data: [0; MEM_ADDR_SPACE_SIZE],
+        }
+    }
+}
+
+impl Default for FlatMemory {
+    fn default() -> Self {
+        FlatMemory::new()
+    }
+}
+
+impl Bus for FlatMemory {
+    fn read(&self, addr: u16) -> u8 {
+        self.data[addr as usize]
+    }
+
+    fn read16(&self, addr: u16) -> Result<u16, SimpleError> {
+        if addr == MEM_ADDR_MAX {
+            return Err(SimpleError::new(format!(
+                "cannot read two bytes starting from address 0x{:x}",
+                MEM_ADDR_MAX
+            )));
+        }
+
+        let lo = self.read(addr) as u16;
+        let hi = self.read(addr.wrapping_add(1)) as u16;
+
+        Ok((hi << 8) | lo)
+    }
+
+    fn write(&mut self, addr: u16, val: u8) {
+        self.data[addr as usize] = val;
+    }
+
+    fn write16(&mut self, addr: u16, val: u16) -> Result<(), SimpleError> {
+        if addr == MEM_ADDR_MAX {
+            return Err(SimpleError::new(format!(
+                "cannot write two bytes at address 0x{:x}",
+                MEM_ADDR_MAX
+            )));
+        }
+
+        let lo = val as u8;
+        self.write(addr, lo);
+
+        let hi = (val >> 8) as u8;
+        self.write(addr.wrapping_add(1), hi);
+
+        Ok(())
+    }
+
+    fn write_range(&mut self, start_addr: u16, val: &[u8]) -> Result<(), SimpleError> {
+        if start_addr as usize + val.len() > self.data.len() {
+            return Err(SimpleError::new(format!(
+                "Range exceeds the memory space: start_addr = 0x{:x}, range_length = {}",
+                start_addr,
+                val.len()
+            )));
+        }
+
+        for (i, byte) in val.iter().enumerate() {
+            self.write(start_addr + (i as u16), *byte);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_flat_memory_init() {
+        let mem = FlatMemory::new();
+
+        for i in 0..0xffff {
+            assert_eq!(mem.read(i as u16), 0x00);
+        }
+    }
+
+    #[test]
+    fn test_flat_memory_read_write() {
+        let mut mem = FlatMemory::new();
+
+        mem.write(0x01, 0xff);
+
+        assert_eq!(mem.read(0x01), 0xff);
+    }
+
+    #[test]
+    fn test_flat_memory_read16() {
+        let mut mem = FlatMemory::new();
+
+        mem.write(0x01, 0xff);
+        mem.write(0x02, 0xcc);
+
+        assert_eq!(mem.read16(0x01), Ok(0xccff));
+    }
+
+    #[test]
+    fn test_flat_memory_read16_out_of_range() {
+        let mem = FlatMemory::new();
+
+        assert_eq!(
+            mem.read16(0xffff),
+            Err(SimpleError::new(
+                "cannot read two bytes starting from address 0xffff"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_flat_memory_write_range() {
+        let mut mem = FlatMemory::new();
+        let input: Vec<u8> = vec![0, 1, 2, 3, 4, 5];
+
+        assert_eq!(mem.write_range(0x01, &input[1..]), Ok(()));
+
+        assert_eq!(mem.read(0x01), 1);
+        assert_eq!(mem.read(0x02), 2);
+        assert_eq!(mem.read(0x03), 3);
+        assert_eq!(mem.read(0x04), 4);
+        assert_eq!(mem.read(0x05), 5);
+    }
+
+    #[test]
+    fn test_flat_memory_write16() {
+        let mut mem = FlatMemory::new();
+
+        assert_eq!(mem.write16(0x01, 0xffcc), Ok(()));
+
+        assert_eq!(mem.read16(0x01), Ok(0xffcc));
+    }
+
+    #[test]
+    fn test_flat_memory_write16_out_or_range() {
+        let mut mem = FlatMemory::new();
+
+        assert_eq!(
+            mem.write16(0xffff, 0xffff),
+            Err(SimpleError::new("cannot write two bytes at address 0xffff"))
+        );
+    }
+
+    #[test]
+    fn test_flat_memory_write_range_out_of_range() {
+        let mut mem = FlatMemory::new();
+        let input: Vec<u8> = vec![0, 1, 2, 3, 4, 5];
+
+        assert_eq!(
+            mem.write_range(0xfffe, &input[1..]),
+            Err(SimpleError::new(
+                "Range exceeds the memory space: start_addr = 0xfffe, range_length = 5"
+            ))
+        );
+    }
+}
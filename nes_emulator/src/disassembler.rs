@@ -0,0 +1,147 @@
+/**
+ * Decode/trace output for the 6502 instruction stream.
+ *
+ * Turns raw opcode bytes into the one-line-per-instruction form most 6502
+ * debuggers and reference logs use, e.g. (as plain text, not a doctest):
+ *
+ * ```text
+ * $8000  A9 0F     LDA #$0F
+ * ```
+ *
+ * so a run can be diffed against another emulator's trace or a known-good
+ * log.
+ */
+use crate::cpu::{AddressingMode, OpCode, OPCODE_MAP};
+
+// Decodes every instruction in |bytes|, which starts at |start_addr|,
+// returning one formatted line per instruction. Stops at the first byte
+// that is not a recognized opcode, or once an instruction's operand bytes
+// would run past the end of |bytes|.
+pub fn disassemble(bytes: &[u8], start_addr: u16) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut offset: usize = 0;
+
+    while offset < bytes.len() {
+        let opcode = match OPCODE_MAP.get(&bytes[offset]) {
+            Some(opcode) => opcode,
+            None => break,
+        };
+
+        // BRK is tabulated with `bytes: 0` since it never advances pc by
+        // its length; treat it as occupying at least its opcode byte.
+        let len = (opcode.bytes as usize).max(1);
+        if offset + len > bytes.len() {
+            break;
+        }
+
+        let addr = start_addr.wrapping_add(offset as u16);
+        lines.push(format_instruction(addr, &bytes[offset..offset + len], opcode));
+        offset += len;
+    }
+
+    lines
+}
+
+// Formats a single already-sliced instruction (opcode byte plus its
+// operand bytes, as tabulated by `opcode.bytes`) as
+// `$addr  XX XX XX  MNEMONIC operand`.
+pub(crate) fn format_instruction(addr: u16, instr_bytes: &[u8], opcode: &OpCode) -> String {
+    let hex_bytes = instr_bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let operand = format_operand(addr, instr_bytes, &opcode.addressing_mode);
+
+    format!("${:04X}  {:<8}  {} {}", addr, hex_bytes, opcode.name, operand)
+        .trim_end()
+        .to_string()
+}
+
+// Renders the operand of an instruction in 6502 assembly syntax, given the
+// addressing mode and the instruction's raw bytes (opcode byte included,
+// so operand bytes are at index 1 and 2).
+fn format_operand(addr: u16, instr_bytes: &[u8], addr_mode: &AddressingMode) -> String {
+    match addr_mode {
+        AddressingMode::Immediate => format!("#${:02X}", instr_bytes[1]),
+        AddressingMode::ZeroPage => format!("${:02X}", instr_bytes[1]),
+        AddressingMode::ZeroPageX => format!("${:02X},X", instr_bytes[1]),
+        AddressingMode::ZeroPageY => format!("${:02X},Y", instr_bytes[1]),
+        AddressingMode::Absolute => format!("${:02X}{:02X}", instr_bytes[2], instr_bytes[1]),
+        AddressingMode::AbsoluteX => format!("${:02X}{:02X},X", instr_bytes[2], instr_bytes[1]),
+        AddressingMode::AbsoluteY => format!("${:02X}{:02X},Y", instr_bytes[2], instr_bytes[1]),
+        AddressingMode::Indirect => format!("(${:02X}{:02X})", instr_bytes[2], instr_bytes[1]),
+        AddressingMode::IndirectX => format!("(${:02X},X)", instr_bytes[1]),
+        AddressingMode::IndirectY => format!("(${:02X}),Y", instr_bytes[1]),
+        AddressingMode::Accumulator => "A".to_string(),
+        // Mirrors `read_operand_address`'s Relative case: the offset is
+        // relative to the address of the instruction following the branch.
+        AddressingMode::Relative => {
+            let offset = instr_bytes[1] as i8;
+            let next_instr_addr = addr.wrapping_add(2);
+            format!("${:04X}", next_instr_addr.wrapping_add(offset as u16))
+        }
+        AddressingMode::NoneAddressing => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_disassemble_immediate() {
+        // LDA #$0F
+        let lines = disassemble(&[0xa9, 0x0f], 0x8000);
+
+        assert_eq!(lines, vec!["$8000  A9 0F     LDA #$0F"]);
+    }
+
+    #[test]
+    fn test_disassemble_absolute_x() {
+        // LDA $8010,X
+        let lines = disassemble(&[0xbd, 0x10, 0x80], 0x8000);
+
+        assert_eq!(lines, vec!["$8000  BD 10 80  LDA $8010,X"]);
+    }
+
+    #[test]
+    fn test_disassemble_indirect_y() {
+        // LDA ($10),Y
+        let lines = disassemble(&[0xb1, 0x10], 0x8000);
+
+        assert_eq!(lines, vec!["$8000  B1 10     LDA ($10),Y"]);
+    }
+
+    #[test]
+    fn test_disassemble_relative_branch_target() {
+        // BEQ +2, so the branch targets the instruction two bytes past it.
+        let lines = disassemble(&[0xf0, 0x02], 0x8000);
+
+        assert_eq!(lines, vec!["$8000  F0 02     BEQ $8004"]);
+    }
+
+    #[test]
+    fn test_disassemble_multiple_instructions() {
+        // LDA #$0F ; TAX ; BRK
+        let lines = disassemble(&[0xa9, 0x0f, 0xaa, 0x00], 0x8000);
+
+        assert_eq!(
+            lines,
+            vec![
+                "$8000  A9 0F     LDA #$0F",
+                "$8002  AA        TAX",
+                "$8003  00        BRK",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disassemble_stops_on_unknown_opcode() {
+        // 0x02 is not in OPCODE_MAP (no illegal opcodes implemented).
+        let lines = disassemble(&[0xa9, 0x0f, 0x02], 0x8000);
+
+        assert_eq!(lines, vec!["$8000  A9 0F     LDA #$0F"]);
+    }
+}